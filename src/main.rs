@@ -1,24 +1,109 @@
 use anyhow::{anyhow, Context, Result};
-use clap::{Parser, Subcommand};
-use indicatif::{ProgressBar, ProgressStyle};
+use clap::{Parser, Subcommand, ValueEnum};
+use indicatif::{MultiProgress, ProgressBar, ProgressStyle};
 use serde::Deserialize;
+use sha2::{Digest, Sha256};
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+use std::io::IsTerminal;
 use std::process::Stdio;
 use std::sync::Arc;
+use std::time::Duration;
+use tokio::io::AsyncReadExt;
 use tokio::process::Command;
 use tokio::sync::Semaphore;
+use tokio::time::sleep;
 
-/// Maximum number of concurrent package operations to prevent CPU overload
+/// Default number of concurrent package operations to prevent CPU overload
 const MAX_CONCURRENT_OPERATIONS: usize = 4;
 
+/// Default number of packages per `brew install`/`reinstall` invocation
+const DEFAULT_BATCH_SIZE: usize = 10;
+
 /// Better Brew - Parallel Homebrew package manager
 #[derive(Parser)]
 #[command(name = "bbrew")]
 #[command(about = "Parallel Homebrew package downloads and upgrades", long_about = None)]
 struct Cli {
+    /// Output format: `human` (indicatif bars), `json` (NDJSON events), or
+    /// `auto` to pick based on whether stderr is a terminal
+    #[arg(long, value_enum, global = true, default_value_t = OutputMode::Auto)]
+    output: OutputMode,
+
+    /// Maximum number of concurrent operations (overrides the config file)
+    #[arg(long, global = true)]
+    jobs: Option<usize>,
+
+    /// Number of packages per batched install/reinstall (overrides the config file)
+    #[arg(long, global = true)]
+    batch_size: Option<usize>,
+
+    /// Number of attempts per package before a transient failure is fatal
+    #[arg(long, global = true, default_value_t = 3)]
+    retries: usize,
+
     #[command(subcommand)]
     command: Commands,
 }
 
+/// User configuration read from `~/.config/bbrew/config.toml`. Any value left
+/// unset falls through to the built-in default.
+#[derive(Debug, Default, Deserialize)]
+struct Config {
+    jobs: Option<usize>,
+    batch_size: Option<usize>,
+}
+
+/// Load `~/.config/bbrew/config.toml`, returning defaults when it is absent or
+/// cannot be parsed.
+fn load_config() -> Config {
+    // Use the XDG config base (`~/.config`) explicitly so the path is the same
+    // on macOS — the primary platform for a Homebrew tool — where
+    // `dirs::config_dir()` would otherwise resolve to `~/Library/Application Support`.
+    let Some(path) = dirs::home_dir().map(|d| d.join(".config").join("bbrew").join("config.toml"))
+    else {
+        return Config::default();
+    };
+
+    match std::fs::read_to_string(&path) {
+        Ok(contents) => match toml::from_str(&contents) {
+            Ok(config) => config,
+            Err(e) => {
+                eprintln!("Warning: failed to parse {}: {}", path.display(), e);
+                Config::default()
+            }
+        },
+        Err(_) => Config::default(),
+    }
+}
+
+/// How progress is surfaced to the caller
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum OutputMode {
+    /// Decide from the terminal: bars on a TTY, NDJSON otherwise
+    Auto,
+    /// Interactive `indicatif` progress bars
+    Human,
+    /// One NDJSON event per state change, for scripting and CI
+    Json,
+}
+
+impl OutputMode {
+    /// Resolve `Auto` to a concrete mode based on whether stderr is a terminal
+    fn resolve(self) -> OutputMode {
+        match self {
+            OutputMode::Auto => {
+                if std::io::stderr().is_terminal() {
+                    OutputMode::Human
+                } else {
+                    OutputMode::Json
+                }
+            }
+            other => other,
+        }
+    }
+}
+
 #[derive(Subcommand)]
 enum Commands {
     /// Update Homebrew and fetch latest package definitions
@@ -38,6 +123,11 @@ enum Commands {
         /// List of packages to reinstall (ignored if --all is specified)
         packages: Vec<String>,
     },
+    /// Verify cached bottle downloads against their recorded sha256
+    Verify {
+        /// List of packages to verify (defaults to all installed formulae)
+        packages: Vec<String>,
+    },
 }
 
 /// Represents outdated formulae from `brew outdated --json`
@@ -52,6 +142,42 @@ struct Package {
     name: String,
 }
 
+/// Represents the relevant slice of `brew info --json=v2 <pkg>`
+#[derive(Debug, Deserialize)]
+struct InfoV2 {
+    formulae: Vec<FormulaInfo>,
+}
+
+#[derive(Debug, Deserialize)]
+struct FormulaInfo {
+    bottle: Option<Bottle>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Bottle {
+    stable: Option<BottleStable>,
+}
+
+#[derive(Debug, Deserialize)]
+struct BottleStable {
+    files: HashMap<String, BottleFile>,
+}
+
+#[derive(Debug, Deserialize)]
+struct BottleFile {
+    sha256: String,
+}
+
+/// Outcome of verifying a single cached bottle
+enum VerifyOutcome {
+    /// Cache file present and its digest matched the recorded sha256
+    Verified,
+    /// Cache file present but its digest did not match
+    Mismatch,
+    /// No cache file found for the package
+    Missing,
+}
+
 /// Check if Homebrew is installed and accessible
 async fn check_homebrew() -> Result<()> {
     let output = Command::new("which")
@@ -70,13 +196,21 @@ async fn check_homebrew() -> Result<()> {
     Ok(())
 }
 
-/// Execute a command and stream output to stdout/stderr
-async fn run_command(cmd: &str, args: &[&str]) -> Result<()> {
-    println!("Running: {} {}", cmd, args.join(" "));
+/// Execute a command, streaming its output. In `Json` mode stdout is discarded
+/// so it can't corrupt the NDJSON stream; the "Running:" notice always goes to
+/// stderr.
+async fn run_command(mode: OutputMode, cmd: &str, args: &[&str]) -> Result<()> {
+    eprintln!("Running: {} {}", cmd, args.join(" "));
+
+    let stdout = if mode == OutputMode::Json {
+        Stdio::null()
+    } else {
+        Stdio::inherit()
+    };
 
     let status = Command::new(cmd)
         .args(args)
-        .stdout(Stdio::inherit())
+        .stdout(stdout)
         .stderr(Stdio::inherit())
         .status()
         .await
@@ -91,7 +225,7 @@ async fn run_command(cmd: &str, args: &[&str]) -> Result<()> {
 
 /// Get list of outdated packages from Homebrew
 async fn get_outdated_packages() -> Result<Vec<String>> {
-    println!("Checking for outdated packages...");
+    eprintln!("Checking for outdated packages...");
 
     let output = Command::new("brew")
         .args(["outdated", "--json"])
@@ -118,7 +252,7 @@ async fn get_outdated_packages() -> Result<Vec<String>> {
 
 /// Get list of installed packages from Homebrew (formulae only, not casks)
 async fn get_installed_packages() -> Result<Vec<String>> {
-    println!("Getting list of installed packages...");
+    eprintln!("Getting list of installed packages...");
 
     let output = Command::new("brew")
         .args(["list", "--formula", "-1"])
@@ -142,60 +276,442 @@ async fn get_installed_packages() -> Result<Vec<String>> {
     Ok(packages)
 }
 
+/// Create a per-operation spinner line attached to the shared `MultiProgress`
+fn spawn_spinner(mp: &MultiProgress, message: String) -> ProgressBar {
+    let spinner = mp.add(ProgressBar::new_spinner());
+    spinner.set_style(
+        ProgressStyle::default_spinner()
+            .template("{spinner:.green} {msg}")
+            .unwrap(),
+    );
+    spinner.enable_steady_tick(Duration::from_millis(100));
+    spinner.set_message(message);
+    spinner
+}
+
+/// The shared indicatif bars used when reporting in `Human` mode
+#[derive(Clone)]
+struct HumanBars {
+    mp: MultiProgress,
+    overall: ProgressBar,
+}
+
+/// Surfaces progress either as `indicatif` bars (`Human`) or as one NDJSON
+/// line per state change (`Json`). Calls scoped to the other mode are no-ops,
+/// so the task functions can emit both human and structured progress without
+/// branching on the mode themselves.
+#[derive(Clone)]
+struct Reporter {
+    mode: OutputMode,
+    bars: Option<HumanBars>,
+}
+
+impl Reporter {
+    /// Build a reporter for a run of `len` operations. In `Human` mode this
+    /// constructs the overall progress bar; in `Json` mode no bars are drawn.
+    fn new(mode: OutputMode, len: u64) -> Self {
+        let bars = match mode {
+            OutputMode::Human => {
+                let mp = MultiProgress::new();
+                let overall = mp.add(ProgressBar::new(len));
+                overall.set_style(
+                    ProgressStyle::default_bar()
+                        .template("{spinner:.green} [{elapsed_precise}] [{bar:40.cyan/blue}] {pos}/{len} ({eta}) {msg}")
+                        .unwrap()
+                        .progress_chars("#>-"),
+                );
+                Some(HumanBars { mp, overall })
+            }
+            _ => None,
+        };
+
+        Reporter { mode, bars }
+    }
+
+    /// Print a decorative line (headers, summaries). Suppressed in `Json` mode
+    /// so stdout carries only parseable NDJSON.
+    fn note(&self, message: impl AsRef<str>) {
+        if self.mode == OutputMode::Human {
+            println!("{}", message.as_ref());
+        }
+    }
+
+    /// Start a spinner line for one in-flight operation (human mode only)
+    fn spinner(&self, message: String) -> Option<ProgressBar> {
+        self.bars.as_ref().map(|b| spawn_spinner(&b.mp, message))
+    }
+
+    /// Emit an NDJSON state-change event (json mode only)
+    fn emit(&self, package: &str, state: &str) {
+        if self.mode == OutputMode::Json {
+            println!("{}", serde_json::json!({ "pkg": package, "state": state }));
+        }
+    }
+
+    /// Emit an NDJSON failure event carrying the error text (json mode only)
+    fn emit_error(&self, package: &str, error: &str) {
+        if self.mode == OutputMode::Json {
+            println!(
+                "{}",
+                serde_json::json!({ "pkg": package, "state": "failed", "error": error })
+            );
+        }
+    }
+
+    /// Print a per-operation result line on the overall bar (human mode only)
+    fn line(&self, message: impl AsRef<str>) {
+        if let Some(b) = &self.bars {
+            b.overall.println(message.as_ref());
+        }
+    }
+
+    /// Advance the overall bar by `n` (human mode only)
+    fn inc(&self, n: u64) {
+        if let Some(b) = &self.bars {
+            b.overall.inc(n);
+        }
+    }
+
+    /// Finish the overall bar with a closing message (human mode only)
+    fn finish(&self, message: &str) {
+        if let Some(b) = &self.bars {
+            b.overall.finish_with_message(message.to_string());
+        }
+    }
+}
+
+/// Build a spinner message, annotating it with the attempt count on a retry
+fn attempt_message(verb: &str, target: &str, attempt: usize, retries: usize) -> String {
+    if attempt > 1 {
+        format!("{} {} (attempt {}/{})…", verb, target, attempt, retries)
+    } else {
+        format!("{} {}…", verb, target)
+    }
+}
+
+/// Decide whether a failing `brew` invocation is worth retrying. Errors such as
+/// a missing formula will never succeed, so we don't waste attempts on them.
+fn is_fatal_error(stderr: &str) -> bool {
+    const FATAL_MARKERS: &[&str] = &[
+        "No such formula",
+        "No available formula",
+        "No such keg",
+        "No cask with this name",
+    ];
+    FATAL_MARKERS.iter().any(|marker| stderr.contains(marker))
+}
+
+/// Run `brew <args>` up to `retries` times, retrying only transient failures
+/// with exponential backoff (1s, 2s, 4s, …) plus a little jitter. `describe`
+/// produces the spinner message for each attempt so callers can surface the
+/// attempt count. The final `Output` is returned for the caller to interpret,
+/// whether it succeeded or exhausted its retries.
+async fn run_brew_with_retry(
+    args: &[&str],
+    retries: usize,
+    spinner: &Option<ProgressBar>,
+    describe: impl Fn(usize) -> String,
+) -> Result<std::process::Output> {
+    let mut attempt = 1;
+    loop {
+        if let Some(spinner) = spinner {
+            spinner.set_message(describe(attempt));
+        }
+
+        let output = Command::new("brew")
+            .args(args)
+            .output()
+            .await
+            .context(format!("Failed to execute 'brew {}'", args.join(" ")))?;
+
+        if output.status.success() {
+            return Ok(output);
+        }
+
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        if attempt >= retries || is_fatal_error(&stderr) {
+            return Ok(output);
+        }
+
+        // Exponential backoff with a small time-derived jitter.
+        let backoff = 1u64 << (attempt - 1);
+        let jitter = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| (d.subsec_nanos() % 250) as u64)
+            .unwrap_or(0);
+        sleep(Duration::from_millis(backoff * 1000 + jitter)).await;
+
+        attempt += 1;
+    }
+}
+
 /// Fetch a single package in the background
-async fn fetch_package(package: &str, semaphore: Arc<Semaphore>, pb: ProgressBar) -> Result<()> {
+async fn fetch_package(
+    package: &str,
+    semaphore: Arc<Semaphore>,
+    reporter: Reporter,
+    retries: usize,
+) -> Result<()> {
     let _permit = semaphore.acquire().await.unwrap();
 
-    pb.set_message(format!("Fetching {}", package));
+    let spinner = reporter.spinner(format!("Fetching {}…", package));
+    reporter.emit(package, "fetching");
 
-    let output = Command::new("brew")
-        .args(["fetch", package])
-        .output()
-        .await
-        .context(format!("Failed to fetch package: {}", package))?;
+    let output = run_brew_with_retry(&["fetch", package], retries, &spinner, |attempt| {
+        attempt_message("Fetching", package, attempt, retries)
+    })
+    .await?;
+
+    if let Some(spinner) = spinner {
+        spinner.finish_and_clear();
+    }
 
     if output.status.success() {
-        pb.println(format!("✓ Fetched: {}", package));
-        pb.inc(1);
+        reporter.line(format!("✓ Fetched: {}", package));
+        reporter.emit(package, "fetched");
+        reporter.inc(1);
         Ok(())
     } else {
         let error_msg = String::from_utf8_lossy(&output.stderr);
-        pb.println(format!("✗ Failed to fetch: {}", package));
-        pb.inc(1);
+        reporter.line(format!("✗ Failed to fetch: {}", package));
+        reporter.emit_error(package, error_msg.trim());
+        reporter.inc(1);
         Err(anyhow!("Failed to fetch {}: {}", package, error_msg))
     }
 }
 
+/// Stream-hash a file with sha256, returning the lowercase hex digest
+async fn hash_file(path: &Path) -> Result<String> {
+    let mut file = tokio::fs::File::open(path)
+        .await
+        .context(format!("Failed to open cache file: {}", path.display()))?;
+
+    let mut hasher = Sha256::new();
+    let mut buf = vec![0u8; 64 * 1024];
+    loop {
+        let n = file
+            .read(&mut buf)
+            .await
+            .context(format!("Failed to read cache file: {}", path.display()))?;
+        if n == 0 {
+            break;
+        }
+        hasher.update(&buf[..n]);
+    }
+
+    Ok(format!("{:x}", hasher.finalize()))
+}
+
+/// Resolve the macOS release codename Homebrew uses in its bottle tags (e.g.
+/// `sonoma`) from `sw_vers -productVersion`.
+async fn macos_codename() -> Option<String> {
+    let output = Command::new("sw_vers")
+        .arg("-productVersion")
+        .output()
+        .await
+        .ok()?;
+
+    if !output.status.success() {
+        return None;
+    }
+
+    let version = String::from_utf8_lossy(&output.stdout);
+    let major: u32 = version.trim().split('.').next()?.parse().ok()?;
+    let name = match major {
+        15 => "sequoia",
+        14 => "sonoma",
+        13 => "ventura",
+        12 => "monterey",
+        11 => "big_sur",
+        _ => return None,
+    };
+
+    Some(name.to_string())
+}
+
+/// Determine the current platform's Homebrew bottle tag, e.g. `arm64_sonoma`
+/// on Apple Silicon, `ventura` on Intel macOS, or `x86_64_linux` on Linux.
+async fn current_platform_tag() -> Option<String> {
+    let arch = match std::env::consts::ARCH {
+        "aarch64" => "arm64",
+        other => other,
+    };
+
+    match std::env::consts::OS {
+        "macos" => macos_codename().await.map(|codename| {
+            if arch == "arm64" {
+                format!("arm64_{}", codename)
+            } else {
+                codename
+            }
+        }),
+        "linux" => Some(format!("{}_linux", arch)),
+        _ => None,
+    }
+}
+
+/// Extract the recorded bottle sha256 for `package`, selecting the bottle for
+/// the current platform tag (falling back to the cached archive's file name).
+async fn bottle_sha256(package: &str, cache_name: &str) -> Result<String> {
+    let output = Command::new("brew")
+        .args(["info", "--json=v2", package])
+        .output()
+        .await
+        .context(format!("Failed to execute 'brew info --json=v2 {}'", package))?;
+
+    if !output.status.success() {
+        return Err(anyhow!(
+            "Failed to get info for {}: {}",
+            package,
+            String::from_utf8_lossy(&output.stderr)
+        ));
+    }
+
+    let info: InfoV2 = serde_json::from_slice(&output.stdout)
+        .context(format!("Failed to parse JSON output from brew info for {}", package))?;
+
+    let files = info
+        .formulae
+        .into_iter()
+        .next()
+        .and_then(|f| f.bottle)
+        .and_then(|b| b.stable)
+        .map(|s| s.files)
+        .ok_or_else(|| anyhow!("No stable bottle recorded for {}", package))?;
+
+    // Prefer the bottle recorded for the current platform tag.
+    if let Some(tag) = current_platform_tag().await {
+        if let Some(file) = files.get(&tag) {
+            return Ok(file.sha256.clone());
+        }
+    }
+
+    // Fall back to whichever tag is embedded in the cached archive's name
+    // (e.g. `…--wget--1.21.4.arm64_sonoma.bottle.tar.gz`), then to the sole
+    // entry when the formula has a single platform. The tag is matched together
+    // with its surrounding `.` and `.bottle` so `sonoma` can't match `arm64_sonoma`.
+    if let Some((_, file)) = files
+        .iter()
+        .find(|(tag, _)| cache_name.contains(&format!(".{}.bottle", tag)))
+    {
+        return Ok(file.sha256.clone());
+    }
+
+    if files.len() == 1 {
+        return Ok(files.into_values().next().unwrap().sha256);
+    }
+
+    Err(anyhow!(
+        "No bottle matching the current platform for {} (cached as '{}')",
+        package,
+        cache_name
+    ))
+}
+
+/// Verify a single package's cached bottle against its recorded sha256
+async fn verify_package(
+    package: &str,
+    semaphore: Arc<Semaphore>,
+    reporter: Reporter,
+) -> Result<VerifyOutcome> {
+    let _permit = semaphore.acquire().await.unwrap();
+
+    let spinner = reporter.spinner(format!("Verifying {}…", package));
+    reporter.emit(package, "verifying");
+
+    let output = Command::new("brew")
+        .args(["--cache", package])
+        .output()
+        .await
+        .context(format!("Failed to execute 'brew --cache {}'", package))?;
+
+    if !output.status.success() {
+        if let Some(spinner) = spinner {
+            spinner.finish_and_clear();
+        }
+        let error_msg = String::from_utf8_lossy(&output.stderr);
+        reporter.emit_error(package, error_msg.trim());
+        reporter.inc(1);
+        return Err(anyhow!("Failed to locate cache for {}: {}", package, error_msg));
+    }
+
+    let cache_path = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    let path = Path::new(&cache_path);
+
+    if !path.exists() {
+        if let Some(spinner) = spinner {
+            spinner.finish_and_clear();
+        }
+        reporter.line(format!("✗ {} (cache file missing)", package));
+        reporter.emit(package, "missing");
+        reporter.inc(1);
+        return Ok(VerifyOutcome::Missing);
+    }
+
+    let cache_name = path
+        .file_name()
+        .map(|n| n.to_string_lossy().into_owned())
+        .unwrap_or_default();
+
+    let expected = bottle_sha256(package, &cache_name).await?;
+    let actual = hash_file(path).await?;
+
+    if let Some(spinner) = spinner {
+        spinner.finish_and_clear();
+    }
+    reporter.inc(1);
+    if actual.eq_ignore_ascii_case(&expected) {
+        reporter.line(format!("✓ {}", package));
+        reporter.emit(package, "verified");
+        Ok(VerifyOutcome::Verified)
+    } else {
+        reporter.line(format!("✗ {} (digest mismatch)", package));
+        reporter.emit(package, "mismatch");
+        Ok(VerifyOutcome::Mismatch)
+    }
+}
+
 /// Install a batch of packages in a single brew command
 async fn install_package_batch(
     packages: Vec<String>,
     semaphore: Arc<Semaphore>,
-    pb: ProgressBar,
+    reporter: Reporter,
+    retries: usize,
 ) -> Result<Vec<String>> {
     let _permit = semaphore.acquire().await.unwrap();
 
     let batch_str = packages.join(", ");
-    pb.set_message(format!("Installing batch: {}", batch_str));
+    let spinner = reporter.spinner(format!("Installing {}…", batch_str));
+    for package in &packages {
+        reporter.emit(package, "installing");
+    }
 
     let mut args = vec!["install"];
     args.extend(packages.iter().map(|s| s.as_str()));
 
-    let output = Command::new("brew")
-        .args(&args)
-        .output()
-        .await
-        .context(format!("Failed to install batch: {}", batch_str))?;
+    let output = run_brew_with_retry(&args, retries, &spinner, |attempt| {
+        attempt_message("Installing", &batch_str, attempt, retries)
+    })
+    .await?;
+
+    if let Some(spinner) = spinner {
+        spinner.finish_and_clear();
+    }
 
     if output.status.success() {
         for package in &packages {
-            pb.println(format!("✓ Installed: {}", package));
+            reporter.line(format!("✓ Installed: {}", package));
+            reporter.emit(package, "installed");
         }
-        pb.inc(packages.len() as u64);
+        reporter.inc(packages.len() as u64);
         Ok(vec![])
     } else {
         let error_msg = String::from_utf8_lossy(&output.stderr);
-        pb.println(format!("✗ Batch failed: {}", error_msg.trim()));
-        pb.inc(packages.len() as u64);
+        reporter.line(format!("✗ Batch failed: {}", error_msg.trim()));
+        for package in &packages {
+            reporter.emit_error(package, error_msg.trim());
+        }
+        reporter.inc(packages.len() as u64);
         Ok(packages) // Return failed packages
     }
 }
@@ -204,97 +720,106 @@ async fn install_package_batch(
 async fn reinstall_package_batch(
     packages: Vec<String>,
     semaphore: Arc<Semaphore>,
-    pb: ProgressBar,
+    reporter: Reporter,
+    retries: usize,
 ) -> Result<Vec<String>> {
     let _permit = semaphore.acquire().await.unwrap();
 
     let batch_str = packages.join(", ");
-    pb.set_message(format!("Reinstalling batch: {}", batch_str));
+    let spinner = reporter.spinner(format!("Reinstalling {}…", batch_str));
+    for package in &packages {
+        reporter.emit(package, "reinstalling");
+    }
 
     let mut args = vec!["reinstall"];
     args.extend(packages.iter().map(|s| s.as_str()));
 
-    let output = Command::new("brew")
-        .args(&args)
-        .output()
-        .await
-        .context(format!("Failed to reinstall batch: {}", batch_str))?;
+    let output = run_brew_with_retry(&args, retries, &spinner, |attempt| {
+        attempt_message("Reinstalling", &batch_str, attempt, retries)
+    })
+    .await?;
+
+    if let Some(spinner) = spinner {
+        spinner.finish_and_clear();
+    }
 
     if output.status.success() {
         for package in &packages {
-            pb.println(format!("✓ Reinstalled: {}", package));
+            reporter.line(format!("✓ Reinstalled: {}", package));
+            reporter.emit(package, "reinstalled");
         }
-        pb.inc(packages.len() as u64);
+        reporter.inc(packages.len() as u64);
         Ok(vec![])
     } else {
         let error_msg = String::from_utf8_lossy(&output.stderr);
-        pb.println(format!("✗ Batch failed: {}", error_msg.trim()));
-        pb.inc(packages.len() as u64);
+        reporter.line(format!("✗ Batch failed: {}", error_msg.trim()));
+        for package in &packages {
+            reporter.emit_error(package, error_msg.trim());
+        }
+        reporter.inc(packages.len() as u64);
         Ok(packages) // Return failed packages
     }
 }
 
 /// Parallel update command - updates Homebrew itself
-async fn update() -> Result<()> {
-    println!("=== Better Brew Update ===\n");
+async fn update(mode: OutputMode) -> Result<()> {
+    eprintln!("=== Better Brew Update ===\n");
 
     check_homebrew().await?;
 
     // Run brew update
-    run_command("brew", &["update"]).await?;
+    run_command(mode, "brew", &["update"]).await?;
 
-    println!("\n✓ Update complete!");
+    eprintln!("\n✓ Update complete!");
     Ok(())
 }
 
 /// Parallel upgrade command - fetches packages in parallel then upgrades
-async fn upgrade() -> Result<()> {
-    println!("=== Better Brew Upgrade ===\n");
-
+async fn upgrade(mode: OutputMode, jobs: usize, retries: usize) -> Result<()> {
     check_homebrew().await?;
 
     // Step 1: Update package definitions first
-    println!("Updating package definitions...");
-    run_command("brew", &["update"]).await?;
-    println!();
+    if mode == OutputMode::Human {
+        println!("=== Better Brew Upgrade ===\n");
+        println!("Updating package definitions...");
+        run_command(mode, "brew", &["update"]).await?;
+        println!();
+    } else {
+        run_command(mode, "brew", &["update"]).await?;
+    }
 
     // Step 2: Get outdated packages
     let packages = get_outdated_packages().await?;
 
+    let reporter = Reporter::new(mode, packages.len() as u64);
+
     if packages.is_empty() {
-        println!("✓ All packages are up to date!");
+        reporter.note("✓ All packages are up to date!");
         return Ok(());
     }
 
-    println!(
+    reporter.note(format!(
         "Found {} outdated package(s): {}\n",
         packages.len(),
         packages.join(", ")
-    );
+    ));
 
     // Step 3: Fetch all packages in parallel (with concurrency limit)
-    println!(
+    reporter.note(format!(
         "Fetching packages with {} concurrent operations...",
-        MAX_CONCURRENT_OPERATIONS
-    );
+        jobs
+    ));
 
-    let semaphore = Arc::new(Semaphore::new(MAX_CONCURRENT_OPERATIONS));
-    let pb = ProgressBar::new(packages.len() as u64);
-    pb.set_style(
-        ProgressStyle::default_bar()
-            .template("{spinner:.green} [{elapsed_precise}] [{bar:40.cyan/blue}] {pos}/{len} ({eta}) {msg}")
-            .unwrap()
-            .progress_chars("#>-")
-    );
+    let semaphore = Arc::new(Semaphore::new(jobs));
 
     let fetch_tasks: Vec<_> = packages
         .iter()
-        .map(|package| fetch_package(package, semaphore.clone(), pb.clone()))
+        .map(|package| fetch_package(package, semaphore.clone(), reporter.clone(), retries))
         .collect();
 
     // Wait for all fetches to complete
     let results = futures::future::join_all(fetch_tasks).await;
-    pb.finish_with_message("Fetching complete");
+    reporter.finish("Fetching complete");
 
     // Check for any failures
     let mut failed = Vec::new();
@@ -314,76 +839,257 @@ async fn upgrade() -> Result<()> {
         );
     }
 
-    println!("\n=== Installing upgrades ===\n");
+    reporter.note("\n=== Installing upgrades ===\n");
 
     // Step 4: Run brew upgrade (will use pre-fetched packages)
-    run_command("brew", &["upgrade"]).await?;
+    run_command(mode, "brew", &["upgrade"]).await?;
 
-    println!("\n✓ Upgrade complete!");
+    reporter.note("\n✓ Upgrade complete!");
     Ok(())
 }
 
-/// Parallel install command - installs packages in parallel
-async fn install(packages: Vec<String>) -> Result<()> {
-    println!("=== Better Brew Install ===\n");
-
+/// Parallel verify command - checksum-validates cached bottle downloads
+async fn verify(mode: OutputMode, jobs: usize, packages: Vec<String>) -> Result<()> {
     check_homebrew().await?;
 
-    if packages.is_empty() {
-        return Err(anyhow!("No packages specified to install"));
+    let packages_to_verify = if packages.is_empty() {
+        get_installed_packages().await?
+    } else {
+        packages
+    };
+
+    let reporter = Reporter::new(mode, packages_to_verify.len() as u64);
+    reporter.note("=== Better Brew Verify ===\n");
+
+    if packages_to_verify.is_empty() {
+        reporter.note("✓ No packages to verify!");
+        return Ok(());
     }
 
-    println!(
-        "Installing {} package(s)\n",
-        packages.len()
-    );
+    reporter.note(format!(
+        "Verifying {} package(s) with {} concurrent operations...\n",
+        packages_to_verify.len(),
+        jobs
+    ));
+
+    let semaphore = Arc::new(Semaphore::new(jobs));
 
-    // Batch packages to reduce lock contention while maintaining parallelism
-    // Each batch runs `brew install pkg1 pkg2 pkg3...` which Homebrew handles efficiently
-    const BATCH_SIZE: usize = 10;
-    let batches: Vec<Vec<String>> = packages
-        .chunks(BATCH_SIZE)
-        .map(|chunk| chunk.to_vec())
+    let verify_tasks: Vec<_> = packages_to_verify
+        .iter()
+        .map(|package| verify_package(package, semaphore.clone(), reporter.clone()))
         .collect();
 
-    println!(
-        "Installing in {} batch(es) with {} concurrent operations...",
-        batches.len(),
-        MAX_CONCURRENT_OPERATIONS
-    );
+    let results = futures::future::join_all(verify_tasks).await;
+    reporter.finish("Verification complete");
 
-    let semaphore = Arc::new(Semaphore::new(MAX_CONCURRENT_OPERATIONS));
-    let pb = ProgressBar::new(packages.len() as u64);
-    pb.set_style(
-        ProgressStyle::default_bar()
-            .template("{spinner:.green} [{elapsed_precise}] [{bar:40.cyan/blue}] {pos}/{len} ({eta}) {msg}")
-            .unwrap()
-            .progress_chars("#>-")
-    );
+    // Collect anything that didn't cleanly verify
+    let mut problems = Vec::new();
+    for (i, result) in results.into_iter().enumerate() {
+        match result {
+            Ok(VerifyOutcome::Verified) => {}
+            Ok(VerifyOutcome::Mismatch) => {
+                problems.push(format!("{} (digest mismatch)", packages_to_verify[i]))
+            }
+            Ok(VerifyOutcome::Missing) => {
+                problems.push(format!("{} (cache file missing)", packages_to_verify[i]))
+            }
+            Err(e) => {
+                eprintln!("✗ Error: {}", e);
+                problems.push(format!("{} (error)", packages_to_verify[i]));
+            }
+        }
+    }
 
-    let install_tasks: Vec<_> = batches
-        .into_iter()
-        .map(|batch| install_package_batch(batch, semaphore.clone(), pb.clone()))
+    let verified = packages_to_verify.len() - problems.len();
+
+    reporter.note("");
+    if verified > 0 {
+        reporter.note(format!("✓ {} package(s) verified", verified));
+    }
+
+    if !problems.is_empty() {
+        eprintln!(
+            "✗ {} package(s) failed verification:\n  {}",
+            problems.len(),
+            problems.join("\n  ")
+        );
+        return Err(anyhow!("Some packages failed verification"));
+    }
+
+    reporter.note("\n✓ Verify complete!");
+    Ok(())
+}
+
+/// Group `packages` into dependency-ordered layers using Kahn's algorithm.
+///
+/// Edges are restricted to the requested set — a package's dependencies that
+/// aren't themselves being installed don't constrain ordering. Each returned
+/// layer holds packages that are mutually independent and therefore safe to run
+/// concurrently; a layer only starts once every prior layer has finished.
+///
+/// Returns `Ok(None)` when the graph contains a cycle, so the caller can fall
+/// back to flat batching.
+async fn dependency_layers(packages: &[String]) -> Result<Option<Vec<Vec<String>>>> {
+    // Collapse duplicate arguments (e.g. `install wget wget`) up front so the
+    // unique count lines up with the graph; otherwise the dedup inside the maps
+    // below would make `scheduled` fall short and look like a cycle.
+    let mut seen = HashSet::new();
+    let packages: Vec<String> = packages
+        .iter()
+        .filter(|p| seen.insert(p.as_str()))
+        .cloned()
         .collect();
 
-    // Wait for all installs to complete
-    let results = futures::future::join_all(install_tasks).await;
-    pb.finish_with_message("Installation complete");
+    let requested: HashSet<&str> = packages.iter().map(|s| s.as_str()).collect();
+
+    // Edges point from a prerequisite to the package that depends on it.
+    let mut dependents: HashMap<String, Vec<String>> = HashMap::new();
+    let mut in_degree: HashMap<String, usize> =
+        packages.iter().map(|p| (p.clone(), 0)).collect();
+
+    for package in &packages {
+        let output = Command::new("brew")
+            .args(["deps", package])
+            .output()
+            .await
+            .context(format!("Failed to execute 'brew deps {}'", package))?;
+
+        if !output.status.success() {
+            return Err(anyhow!(
+                "Failed to get dependencies for {}: {}",
+                package,
+                String::from_utf8_lossy(&output.stderr)
+            ));
+        }
+
+        for dep in String::from_utf8_lossy(&output.stdout).lines() {
+            let dep = dep.trim();
+            if dep.is_empty() || dep == package || !requested.contains(dep) {
+                continue;
+            }
+            dependents
+                .entry(dep.to_string())
+                .or_default()
+                .push(package.clone());
+            *in_degree.get_mut(package).unwrap() += 1;
+        }
+    }
+
+    let mut layers: Vec<Vec<String>> = Vec::new();
+    let mut scheduled = 0usize;
+
+    loop {
+        let mut layer: Vec<String> = in_degree
+            .iter()
+            .filter(|(_, &degree)| degree == 0)
+            .map(|(pkg, _)| pkg.clone())
+            .collect();
+
+        if layer.is_empty() {
+            break;
+        }
+
+        layer.sort(); // deterministic ordering within a layer
+
+        for pkg in &layer {
+            in_degree.remove(pkg);
+            if let Some(succs) = dependents.get(pkg) {
+                for succ in succs {
+                    if let Some(degree) = in_degree.get_mut(succ) {
+                        *degree -= 1;
+                    }
+                }
+            }
+        }
+
+        scheduled += layer.len();
+        layers.push(layer);
+    }
+
+    if scheduled < packages.len() {
+        // Some nodes never reached in-degree zero: the graph has a cycle.
+        return Ok(None);
+    }
+
+    Ok(Some(layers))
+}
+
+/// Parallel install command - installs packages in parallel
+async fn install(
+    mode: OutputMode,
+    jobs: usize,
+    batch_size: usize,
+    retries: usize,
+    packages: Vec<String>,
+) -> Result<()> {
+    check_homebrew().await?;
+
+    if packages.is_empty() {
+        return Err(anyhow!("No packages specified to install"));
+    }
+
+    let reporter = Reporter::new(mode, packages.len() as u64);
+    reporter.note("=== Better Brew Install ===\n");
+    reporter.note(format!("Installing {} package(s)\n", packages.len()));
+
+    // Each batch runs `brew install pkg1 pkg2 pkg3...` which Homebrew handles
+    // efficiently; batching bounds lock contention within an independent layer.
+    let semaphore = Arc::new(Semaphore::new(jobs));
+
+    // Schedule by dependency layer so a dependent never starts before its
+    // prerequisites, falling back to one flat layer (still split into batches)
+    // on a cycle or error.
+    let layers = match dependency_layers(&packages).await {
+        Ok(Some(layers)) => layers,
+        Ok(None) => {
+            eprintln!("Warning: dependency cycle detected; falling back to flat batching");
+            vec![packages.clone()]
+        }
+        Err(e) => {
+            eprintln!(
+                "Warning: could not compute dependency graph ({}); falling back to flat batching",
+                e
+            );
+            vec![packages.clone()]
+        }
+    };
+
+    reporter.note(format!(
+        "Scheduling {} package(s) across {} dependency layer(s) with {} concurrent operations:",
+        packages.len(),
+        layers.len(),
+        jobs
+    ));
+    for (i, layer) in layers.iter().enumerate() {
+        reporter.note(format!("  layer {}: {}", i + 1, layer.join(", ")));
+    }
 
-    // Collect failed packages
+    // Run each layer to completion before the next, with the layer's packages
+    // batched and the batches themselves bounded by the semaphore.
     let mut failed = Vec::new();
-    for result in results {
-        match result {
-            Ok(failed_packages) => failed.extend(failed_packages),
-            Err(e) => eprintln!("✗ Error: {}", e),
+    for layer in &layers {
+        let tasks: Vec<_> = layer
+            .chunks(batch_size)
+            .map(|chunk| {
+                install_package_batch(chunk.to_vec(), semaphore.clone(), reporter.clone(), retries)
+            })
+            .collect();
+
+        let results = futures::future::join_all(tasks).await;
+        for result in results {
+            match result {
+                Ok(failed_packages) => failed.extend(failed_packages),
+                Err(e) => eprintln!("✗ Error: {}", e),
+            }
         }
     }
+    reporter.finish("Installation complete");
 
     let succeeded = packages.len() - failed.len();
 
-    println!();
+    reporter.note("");
     if succeeded > 0 {
-        println!("✓ Successfully installed {} package(s)", succeeded);
+        reporter.note(format!("✓ Successfully installed {} package(s)", succeeded));
     }
 
     if !failed.is_empty() {
@@ -395,18 +1101,22 @@ async fn install(packages: Vec<String>) -> Result<()> {
         return Err(anyhow!("Some packages failed to install"));
     }
 
-    println!("\n✓ Install complete!");
+    reporter.note("\n✓ Install complete!");
     Ok(())
 }
 
 /// Parallel reinstall command - reinstalls packages in parallel
-async fn reinstall(all: bool, packages: Vec<String>) -> Result<()> {
-    println!("=== Better Brew Reinstall ===\n");
-
+async fn reinstall(
+    mode: OutputMode,
+    jobs: usize,
+    batch_size: usize,
+    retries: usize,
+    all: bool,
+    packages: Vec<String>,
+) -> Result<()> {
     check_homebrew().await?;
 
     let packages_to_reinstall = if all {
-        println!("Reinstalling ALL installed packages...\n");
         get_installed_packages().await?
     } else {
         if packages.is_empty() {
@@ -417,62 +1127,85 @@ async fn reinstall(all: bool, packages: Vec<String>) -> Result<()> {
         packages
     };
 
+    let reporter = Reporter::new(mode, packages_to_reinstall.len() as u64);
+    reporter.note("=== Better Brew Reinstall ===\n");
+    if all {
+        reporter.note("Reinstalling ALL installed packages...\n");
+    }
+
     if packages_to_reinstall.is_empty() {
-        println!("✓ No packages to reinstall!");
+        reporter.note("✓ No packages to reinstall!");
         return Ok(());
     }
 
-    println!(
+    reporter.note(format!(
         "Reinstalling {} package(s)\n",
         packages_to_reinstall.len()
-    );
-
-    // Batch packages to reduce lock contention while maintaining parallelism
-    // Each batch runs `brew reinstall pkg1 pkg2 pkg3...` which Homebrew handles efficiently
-    const BATCH_SIZE: usize = 10;
-    let batches: Vec<Vec<String>> = packages_to_reinstall
-        .chunks(BATCH_SIZE)
-        .map(|chunk| chunk.to_vec())
-        .collect();
-
-    println!(
-        "Reinstalling in {} batch(es) with {} concurrent operations...",
-        batches.len(),
-        MAX_CONCURRENT_OPERATIONS
-    );
-
-    let semaphore = Arc::new(Semaphore::new(MAX_CONCURRENT_OPERATIONS));
-    let pb = ProgressBar::new(packages_to_reinstall.len() as u64);
-    pb.set_style(
-        ProgressStyle::default_bar()
-            .template("{spinner:.green} [{elapsed_precise}] [{bar:40.cyan/blue}] {pos}/{len} ({eta}) {msg}")
-            .unwrap()
-            .progress_chars("#>-")
-    );
-
-    let reinstall_tasks: Vec<_> = batches
-        .into_iter()
-        .map(|batch| reinstall_package_batch(batch, semaphore.clone(), pb.clone()))
-        .collect();
+    ));
+
+    // Each batch runs `brew reinstall pkg1 pkg2 pkg3...` which Homebrew handles
+    // efficiently; batching bounds lock contention within an independent layer.
+    let semaphore = Arc::new(Semaphore::new(jobs));
+
+    // Schedule by dependency layer so a dependent never starts before its
+    // prerequisites, falling back to one flat layer (still split into batches)
+    // on a cycle or error.
+    let layers = match dependency_layers(&packages_to_reinstall).await {
+        Ok(Some(layers)) => layers,
+        Ok(None) => {
+            eprintln!("Warning: dependency cycle detected; falling back to flat batching");
+            vec![packages_to_reinstall.clone()]
+        }
+        Err(e) => {
+            eprintln!(
+                "Warning: could not compute dependency graph ({}); falling back to flat batching",
+                e
+            );
+            vec![packages_to_reinstall.clone()]
+        }
+    };
 
-    // Wait for all reinstalls to complete
-    let results = futures::future::join_all(reinstall_tasks).await;
-    pb.finish_with_message("Reinstallation complete");
+    reporter.note(format!(
+        "Scheduling {} package(s) across {} dependency layer(s) with {} concurrent operations:",
+        packages_to_reinstall.len(),
+        layers.len(),
+        jobs
+    ));
+    for (i, layer) in layers.iter().enumerate() {
+        reporter.note(format!("  layer {}: {}", i + 1, layer.join(", ")));
+    }
 
-    // Collect failed packages
+    // Run each layer to completion before the next, with the layer's packages
+    // batched and the batches themselves bounded by the semaphore.
     let mut failed = Vec::new();
-    for result in results {
-        match result {
-            Ok(failed_packages) => failed.extend(failed_packages),
-            Err(e) => eprintln!("✗ Error: {}", e),
+    for layer in &layers {
+        let tasks: Vec<_> = layer
+            .chunks(batch_size)
+            .map(|chunk| {
+                reinstall_package_batch(
+                    chunk.to_vec(),
+                    semaphore.clone(),
+                    reporter.clone(),
+                    retries,
+                )
+            })
+            .collect();
+
+        let results = futures::future::join_all(tasks).await;
+        for result in results {
+            match result {
+                Ok(failed_packages) => failed.extend(failed_packages),
+                Err(e) => eprintln!("✗ Error: {}", e),
+            }
         }
     }
+    reporter.finish("Reinstallation complete");
 
     let succeeded = packages_to_reinstall.len() - failed.len();
 
-    println!();
+    reporter.note("");
     if succeeded > 0 {
-        println!("✓ Successfully reinstalled {} package(s)", succeeded);
+        reporter.note(format!("✓ Successfully reinstalled {} package(s)", succeeded));
     }
 
     if !failed.is_empty() {
@@ -484,19 +1217,39 @@ async fn reinstall(all: bool, packages: Vec<String>) -> Result<()> {
         return Err(anyhow!("Some packages failed to reinstall"));
     }
 
-    println!("\n✓ Reinstall complete!");
+    reporter.note("\n✓ Reinstall complete!");
     Ok(())
 }
 
 #[tokio::main]
 async fn main() -> Result<()> {
     let cli = Cli::parse();
+    let mode = cli.output.resolve();
+
+    // Precedence for tunables: command-line flag > config file > built-in default.
+    let config = load_config();
+    let jobs = cli
+        .jobs
+        .or(config.jobs)
+        .unwrap_or(MAX_CONCURRENT_OPERATIONS)
+        .max(1);
+    let batch_size = cli
+        .batch_size
+        .or(config.batch_size)
+        .unwrap_or(DEFAULT_BATCH_SIZE)
+        .max(1);
+    let retries = cli.retries.max(1);
 
     match cli.command {
-        Commands::Update => update().await,
-        Commands::Upgrade => upgrade().await,
-        Commands::Install { packages } => install(packages).await,
-        Commands::Reinstall { all, packages } => reinstall(all, packages).await,
+        Commands::Update => update(mode).await,
+        Commands::Upgrade => upgrade(mode, jobs, retries).await,
+        Commands::Install { packages } => {
+            install(mode, jobs, batch_size, retries, packages).await
+        }
+        Commands::Reinstall { all, packages } => {
+            reinstall(mode, jobs, batch_size, retries, all, packages).await
+        }
+        Commands::Verify { packages } => verify(mode, jobs, packages).await,
     }
 }
 
@@ -530,4 +1283,40 @@ mod tests {
         assert_eq!(outdated.casks.len(), 1);
         assert_eq!(outdated.formulae[0].name, "wget");
     }
+
+    #[test]
+    fn test_bottle_info_parsing() {
+        let json = r#"{
+            "formulae": [
+                {
+                    "name": "wget",
+                    "bottle": {
+                        "stable": {
+                            "files": {
+                                "arm64_sonoma": {
+                                    "url": "https://ghcr.io/v2/homebrew/core/wget/blobs/sha256-abc",
+                                    "sha256": "abc"
+                                },
+                                "ventura": {
+                                    "url": "https://ghcr.io/v2/homebrew/core/wget/blobs/sha256-def",
+                                    "sha256": "def"
+                                }
+                            }
+                        }
+                    }
+                }
+            ],
+            "casks": []
+        }"#;
+
+        let info: InfoV2 = serde_json::from_str(json).unwrap();
+        let files = info.formulae[0]
+            .bottle
+            .as_ref()
+            .and_then(|b| b.stable.as_ref())
+            .map(|s| &s.files)
+            .unwrap();
+        assert_eq!(files.len(), 2);
+        assert_eq!(files["arm64_sonoma"].sha256, "abc");
+    }
 }